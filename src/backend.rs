@@ -0,0 +1,108 @@
+//! The [`KeyboardBackend`] trait, which abstracts over the USB identity and wire
+//! protocol of a specific keyboard model.
+
+use crate::{Brightness, Color, PollingRate, Profile, SkillerDevice};
+use rusb::{Context, UsbContext};
+use std::time::Duration;
+
+/// Describes the USB identity and wire protocol of a specific keyboard model.
+///
+/// Implementing this trait for a new device lets it plug into the crate without
+/// touching the public `Color`/`Profile`/`Brightness` API. Each backend is gated
+/// behind its own cargo feature, mirroring how sibling RGB controller crates keep
+/// every supported device behind an independent feature flag. See the
+/// `skiller_pro_plus` feature for the reference implementation.
+pub trait KeyboardBackend {
+    /// The USB interface number this keyboard exposes its control endpoint on
+    fn interface() -> u8;
+
+    /// The USB vendor and product ID identifying this keyboard
+    fn vid_pid() -> (u16, u16);
+
+    /// Builds the payload that switches the keyboard's active profile
+    fn switch_profile(profile: &Profile) -> [u8; 8];
+
+    /// Builds the payload that sets the color of the given profile
+    fn color_payload(color: &Color, profile: &Profile) -> [u8; 8];
+
+    /// Builds the payload that sets the brightness (and color) of the given profile
+    fn brightness_payload(brightness: &Brightness, profile: &Profile) -> [u8; 8];
+
+    /// Builds the payload that sets the global polling rate
+    fn polling_rate_payload(rate: &PollingRate) -> [u8; 8];
+
+    /// Builds the payload that enables or disables the windows key for the given profile
+    fn win_key_payload(enable: bool, profile: &Profile) -> [u8; 8];
+
+    /// Parses a feature report read back from the keyboard into the profile it
+    /// reports, or `None` if the report doesn't currently reflect a profile switch
+    fn parse_profile(report: &[u8; 8]) -> Option<Profile>;
+
+    /// Parses a feature report read back from the keyboard into the polling rate
+    /// it reports, or `None` if the report doesn't currently reflect a polling rate
+    fn parse_polling_rate(report: &[u8; 8]) -> Option<PollingRate>;
+}
+
+/// Returns the VID/PID pairs of every keyboard backend enabled via cargo features.
+///
+/// Used by [`enumerate_devices`]: a new backend's devices are found once its
+/// VID/PID pair is added here, and the backend's own `enumerate` then filters
+/// them by its [`KeyboardBackend::vid_pid`].
+fn enabled_backend_vid_pids() -> Vec<(u16, u16)> {
+    #[allow(unused_mut)]
+    let mut ids = Vec::new();
+
+    #[cfg(feature = "skiller_pro_plus")]
+    ids.push(<crate::SkillerProPlus as KeyboardBackend>::vid_pid());
+
+    ids
+}
+
+/// Enumerates every USB device matching a known backend's VID/PID, without
+/// claiming any of them.
+///
+/// This is backend-agnostic: it returns a device for every enabled backend it
+/// finds, tagged with the VID/PID it matched. Each backend's own `enumerate`
+/// (e.g. `SkillerProPlus::enumerate`) must filter the result down to its own
+/// [`KeyboardBackend::vid_pid`] before opening anything, so a second backend
+/// being enabled doesn't cause one model to be bound as another.
+pub(crate) fn enumerate_devices(timeout: Duration) -> rusb::Result<Vec<SkillerDevice>> {
+    let context = Context::new()?;
+    let devices = context.devices()?;
+    let known_ids = enabled_backend_vid_pids();
+
+    let mut found = Vec::new();
+
+    for device in devices.iter() {
+        let device_desc = device.device_descriptor()?;
+        let vid_pid = (device_desc.vendor_id(), device_desc.product_id());
+
+        if !known_ids.contains(&vid_pid) {
+            continue;
+        }
+
+        let bus_number = device.bus_number();
+        let address = device.address();
+
+        // Reading the serial number requires opening the device, but stops
+        // short of detaching the kernel driver, so enumeration stays read-only
+        // until the caller actually selects a device with `open`.
+        let serial_number = device.open().ok().and_then(|handle| {
+            let language = handle.read_languages(timeout).ok()?.into_iter().next()?;
+            handle
+                .read_serial_number_string(language, &device_desc, timeout)
+                .ok()
+        });
+
+        found.push(SkillerDevice {
+            device,
+            vid_pid,
+            bus_number,
+            address,
+            serial_number,
+            timeout,
+        });
+    }
+
+    Ok(found)
+}
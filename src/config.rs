@@ -0,0 +1,67 @@
+//! A serializable snapshot of the keyboard's full configuration.
+
+use crate::{Brightness, Color, PollingRate};
+
+/// The color, brightness and windows-key state for a single profile.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ProfileConfig {
+    pub color: Color,
+    pub brightness: Brightness,
+    pub win_key: bool,
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        ProfileConfig {
+            color: Color::White,
+            brightness: Brightness::Static {
+                level: 1,
+                color: Color::White,
+            },
+            win_key: true,
+        }
+    }
+}
+
+/// A serializable, full snapshot of the keyboard's configuration across all profiles.
+///
+/// Pairs with `SkillerProPlus::apply` to turn the usual one-setting-at-a-time calls
+/// into a declarative workflow, e.g. loading a theme from a TOML/JSON file. Activate
+/// the `serde` feature to (de)serialize it.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KeyboardConfig {
+    pub p1: ProfileConfig,
+    pub p2: ProfileConfig,
+    pub p3: ProfileConfig,
+    pub polling_rate: PollingRate,
+}
+
+impl Default for KeyboardConfig {
+    fn default() -> Self {
+        KeyboardConfig {
+            p1: ProfileConfig::default(),
+            p2: ProfileConfig::default(),
+            p3: ProfileConfig::default(),
+            polling_rate: PollingRate::HZ500,
+        }
+    }
+}
+
+impl KeyboardConfig {
+    /// Builds a config from explicit per-profile settings and a polling rate.
+    pub fn new(
+        p1: ProfileConfig,
+        p2: ProfileConfig,
+        p3: ProfileConfig,
+        polling_rate: PollingRate,
+    ) -> KeyboardConfig {
+        KeyboardConfig {
+            p1,
+            p2,
+            p3,
+            polling_rate,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! An optional control daemon that keeps one [`SkillerProPlus`] handle open and
+//! exposes it over a Unix domain socket.
+//!
+//! This avoids the repeated detach/re-open churn of creating a new
+//! [`SkillerProPlus`] for every change when many quick changes are issued, e.g.
+//! from scripts or a GUI. Requires the `serde` and `skiller_pro_plus` features.
+
+use crate::{Brightness, Color, KeyboardConfig, PollingRate, Profile, SkillerProPlus};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+
+/// A command sent to the daemon over its socket, one line-delimited JSON value
+/// per request.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Request {
+    SetColor {
+        color: Color,
+        profile: Profile,
+    },
+    SetBrightness {
+        brightness: Brightness,
+        profile: Profile,
+    },
+    SetProfile {
+        profile: Profile,
+    },
+    SetPollingRate {
+        rate: PollingRate,
+    },
+    SetWinKey {
+        enable: bool,
+        profile: Profile,
+    },
+    Apply {
+        config: KeyboardConfig,
+    },
+}
+
+/// The daemon's reply to a [`Request`]: bytes written, or as a string the libusb
+/// error or the error from parsing a malformed request line.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Response {
+    Written(usize),
+    Error(String),
+}
+
+/// Runs the daemon: keeps `skiller` open and serves [`Request`]/[`Response`]
+/// pairs on `socket_path`, one connection at a time, until an I/O error occurs.
+pub fn run(skiller: &SkillerProPlus, socket_path: impl AsRef<Path>) -> std::io::Result<()> {
+    let listener = UnixListener::bind(socket_path)?;
+
+    for stream in listener.incoming() {
+        handle_client(skiller, stream?)?;
+    }
+
+    Ok(())
+}
+
+fn handle_client(skiller: &SkillerProPlus, mut stream: UnixStream) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+
+    while reader.read_line(&mut line)? > 0 {
+        let response = match serde_json::from_str::<Request>(line.trim_end()) {
+            Ok(request) => dispatch(skiller, request),
+            Err(e) => Response::Error(e.to_string()),
+        };
+
+        let encoded = serde_json::to_string(&response).map_err(to_io_error)?;
+        writeln!(stream, "{encoded}")?;
+
+        line.clear();
+    }
+
+    Ok(())
+}
+
+fn dispatch(skiller: &SkillerProPlus, request: Request) -> Response {
+    let result = match request {
+        Request::SetColor { color, profile } => skiller.set_color(color, profile),
+        Request::SetBrightness { brightness, profile } => {
+            skiller.set_brightness(brightness, profile)
+        }
+        Request::SetProfile { profile } => skiller.set_profile(profile),
+        Request::SetPollingRate { rate } => skiller.set_polling_rate(rate),
+        Request::SetWinKey { enable, profile } => skiller.set_win_key(enable, profile),
+        Request::Apply { config } => skiller.apply(&config),
+    };
+
+    match result {
+        Ok(written) => Response::Written(written),
+        Err(e) => Response::Error(e.to_string()),
+    }
+}
+
+/// Connects to a running daemon's socket, for use with [`send`].
+pub fn connect(socket_path: impl AsRef<Path>) -> std::io::Result<UnixStream> {
+    UnixStream::connect(socket_path)
+}
+
+/// Sends `request` to a daemon connected via [`connect`] and waits for its
+/// response.
+pub fn send(stream: &mut UnixStream, request: &Request) -> std::io::Result<Response> {
+    let encoded = serde_json::to_string(request).map_err(to_io_error)?;
+    writeln!(stream, "{encoded}")?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    serde_json::from_str(line.trim_end()).map_err(to_io_error)
+}
+
+fn to_io_error(e: serde_json::Error) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,137 @@
+//! Host-driven lighting effects built on top of the three hardware modes
+//! (Static/Pulsating/Cycle), by rapidly re-issuing `set_brightness`.
+
+#[cfg(feature = "skiller_pro_plus")]
+use crate::SkillerProPlus;
+use crate::{Brightness, Color, Profile};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A host-driven lighting effect.
+///
+/// Implementors describe an animation frame by frame; `SkillerProPlus::run_effect`
+/// drives it by calling `tick` once per frame interval and writing out whatever it
+/// returns, stopping when `tick` returns `None`.
+pub trait Effect {
+    /// Computes the brightness and profile to display at time `t` since the effect
+    /// started, or `None` to stop the effect.
+    fn tick(&mut self, t: Duration) -> Option<(Brightness, Profile)>;
+}
+
+#[cfg(feature = "skiller_pro_plus")]
+impl SkillerProPlus {
+    /// Runs `effect` until it stops itself, a write fails, or `cancel` is set.
+    ///
+    /// Calls `effect.tick` once per `frame_interval` and writes the result with
+    /// `set_brightness`, sleeping `frame_interval` between frames. `cancel` lets a
+    /// caller stop the loop cleanly from another thread.
+    pub fn run_effect(
+        &self,
+        mut effect: impl Effect,
+        frame_interval: Duration,
+        cancel: &AtomicBool,
+    ) -> rusb::Result<()> {
+        let start = Instant::now();
+
+        while !cancel.load(Ordering::Relaxed) {
+            let Some((brightness, profile)) = effect.tick(start.elapsed()) else {
+                break;
+            };
+
+            self.set_brightness(brightness, profile)?;
+
+            thread::sleep(frame_interval);
+        }
+
+        Ok(())
+    }
+}
+
+/// A breathing effect that steps `Brightness::Static` levels up and down between
+/// `min_level` and `max_level` at a fixed color.
+pub struct BreathingEffect {
+    color: Color,
+    profile: Profile,
+    min_level: u8,
+    max_level: u8,
+    level: u8,
+    ascending: bool,
+}
+
+impl BreathingEffect {
+    pub fn new(color: Color, profile: Profile, min_level: u8, max_level: u8) -> BreathingEffect {
+        BreathingEffect {
+            color,
+            profile,
+            min_level,
+            max_level,
+            level: min_level,
+            ascending: true,
+        }
+    }
+}
+
+impl Effect for BreathingEffect {
+    fn tick(&mut self, _t: Duration) -> Option<(Brightness, Profile)> {
+        let brightness = Brightness::Static {
+            level: self.level,
+            color: self.color.clone(),
+        };
+
+        if self.ascending {
+            if self.level >= self.max_level {
+                self.ascending = false;
+            } else {
+                self.level += 1;
+            }
+        } else if self.level <= self.min_level {
+            self.ascending = true;
+        } else {
+            self.level -= 1;
+        }
+
+        Some((brightness, self.profile.clone()))
+    }
+}
+
+const PALETTE_WALK_COLORS: [Color; 7] = [
+    Color::Red,
+    Color::Green,
+    Color::Blue,
+    Color::Purple,
+    Color::Cyan,
+    Color::Yellow,
+    Color::White,
+];
+
+/// A palette walk effect that cycles through all seven `Color` variants at a fixed
+/// brightness level.
+pub struct PaletteWalkEffect {
+    profile: Profile,
+    level: u8,
+    index: usize,
+}
+
+impl PaletteWalkEffect {
+    pub fn new(profile: Profile, level: u8) -> PaletteWalkEffect {
+        PaletteWalkEffect {
+            profile,
+            level,
+            index: 0,
+        }
+    }
+}
+
+impl Effect for PaletteWalkEffect {
+    fn tick(&mut self, _t: Duration) -> Option<(Brightness, Profile)> {
+        let brightness = Brightness::Static {
+            level: self.level,
+            color: PALETTE_WALK_COLORS[self.index].clone(),
+        };
+
+        self.index = (self.index + 1) % PALETTE_WALK_COLORS.len();
+
+        Some((brightness, self.profile.clone()))
+    }
+}
@@ -4,49 +4,100 @@
 //!
 //! To use the types in this libary with clap, activate the `clap` feature.
 //!
+//! ## Multiple keyboards
+//! If more than one keyboard is connected, use `SkillerProPlus::enumerate` to list
+//! them and `SkillerProPlus::open` to bind to a specific one.
+//!
+//! ## Backends
+//! Support for each keyboard model lives behind its own cargo feature and
+//! implements the [`KeyboardBackend`] trait, so other Sharkoon/USB keyboards can
+//! be added without touching the public `Color`/`Profile`/`Brightness` API.
+//! Activate the `skiller_pro_plus` feature for the Skiller Pro+.
+//!
+//! ## Daemon
+//! Activate the `daemon` feature for a long-running control daemon that keeps one
+//! keyboard handle open and exposes it over a Unix domain socket. See the
+//! [`daemon`] module.
+//!
+//! ## Reading back state
+//! `SkillerProPlus` exposes `get_profile` and `get_polling_rate`, but the device
+//! has only one shared feature report that echoes whichever command was written
+//! most recently. That makes them useful only to confirm a just-issued
+//! `set_profile`/`set_polling_rate` reached the device, not to read current
+//! device state at an arbitrary point in time — there is no general "snapshot"
+//! of a keyboard's configuration, and no `get_color` et al. for the same reason.
+//!
 //! ## Note
-//! Since i only have one of these keyboard available to test,
-//! i won't support selecting multiple devices for now, as i
-//! have no way to test this feature.
+//! Since i only have one of these keyboards available to test, enumeration and
+//! selection of a specific device is untested beyond a single connected unit.
 
-use rusb::{request_type, Context, DeviceHandle, Direction, Recipient, RequestType, UsbContext};
+use rusb::Context;
 use std::time::Duration;
 
-const INTERFACE: u8 = 1;
-const VID: u16 = 0x04d9;
-const PID: u16 = 0xa096;
+mod backend;
+mod config;
+#[cfg(feature = "daemon")]
+pub mod daemon;
+pub mod effects;
+#[cfg(feature = "skiller_pro_plus")]
+mod skiller_pro_plus;
+
+pub use backend::KeyboardBackend;
+pub use config::{KeyboardConfig, ProfileConfig};
+#[cfg(feature = "skiller_pro_plus")]
+pub use skiller_pro_plus::SkillerProPlus;
 
-trait ToSkillerBytes {
+pub(crate) trait ToSkillerBytes {
     fn to_skiller_bytes(&self) -> u8;
 }
 
-/// A struct that can interact with the skiller pro plus keyboard
-///
-/// # Example
-/// ```
-/// use libskiller::{SkillerProPlus, Brightness, Color, Profile};
-/// use std::time::Duration;
-///
-/// let skiller = SkillerProPlus::new(Duration::from_secs(2))
-///     .unwrap() // unwrap() possible libusb errors, leaves Option<SkillerProPlus>
-///     .unwrap(); // If this is none, it means that no keyboard was found
-///
-/// skiller
-///     .set_color(Color::Red, Profile::P2)
-///     .unwrap();
+/// The inverse of [`ToSkillerBytes`]: parses a byte read back from the keyboard
+/// into the setting it represents, if it is a recognized value.
+pub(crate) trait FromSkillerBytes: Sized {
+    fn from_skiller_bytes(byte: u8) -> Option<Self>;
+}
+
+/// Identifies a single keyboard discovered via a backend's `enumerate` method, e.g.
+/// [`SkillerProPlus::enumerate`].
 ///
-/// skiller
-///     .set_brightness(Brightness::Pulsating {color: Color::Blue}, Profile::P3)
-///     .unwrap();
-/// ```
+/// Holding a `SkillerDevice` does not open or claim the underlying USB device yet;
+/// pass it to the matching backend's `open` method to bind to it.
 #[derive(Debug, PartialEq, Eq)]
-pub struct SkillerProPlus {
-    handle: DeviceHandle<rusb::Context>,
-    timeout: Duration,
+pub struct SkillerDevice {
+    pub(crate) device: rusb::Device<Context>,
+    pub(crate) vid_pid: (u16, u16),
+    pub(crate) bus_number: u8,
+    pub(crate) address: u8,
+    pub(crate) serial_number: Option<String>,
+    pub(crate) timeout: Duration,
+}
+
+impl SkillerDevice {
+    /// The USB vendor and product ID of this device, identifying which backend's
+    /// `open` it must be passed to
+    pub fn vid_pid(&self) -> (u16, u16) {
+        self.vid_pid
+    }
+
+    /// The USB bus number this device is connected to
+    pub fn bus_number(&self) -> u8 {
+        self.bus_number
+    }
+
+    /// The USB device address on its bus
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    /// The USB serial string of this device, if the device reports one
+    pub fn serial_number(&self) -> Option<&str> {
+        self.serial_number.as_deref()
+    }
 }
 
 /// Represents the different LED colors of the keyboard
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum Color {
     Red,
@@ -72,8 +123,74 @@ impl ToSkillerBytes for Color {
     }
 }
 
+impl Color {
+    /// Maps an arbitrary 24-bit RGB color to the closest color supported by the
+    /// keyboard's hardware palette.
+    ///
+    /// Distance between colors is computed using the "redmean" weighted distance,
+    /// which approximates human color perception better than a plain Euclidean
+    /// distance in RGB space. Ties are broken towards the color with the lower
+    /// `to_skiller_bytes()` index.
+    pub fn from_rgb(r: u8, g: u8, b: u8) -> Color {
+        const PALETTE: [(Color, (u8, u8, u8)); 7] = [
+            (Color::Red, (255, 0, 0)),
+            (Color::Green, (0, 255, 0)),
+            (Color::Blue, (0, 0, 255)),
+            (Color::Purple, (255, 0, 255)),
+            (Color::Cyan, (0, 255, 255)),
+            (Color::Yellow, (255, 255, 0)),
+            (Color::White, (255, 255, 255)),
+        ];
+
+        let mut best = &PALETTE[0];
+        let mut best_dist = redmean_distance((r, g, b), best.1);
+
+        for entry in &PALETTE[1..] {
+            let dist = redmean_distance((r, g, b), entry.1);
+            if dist < best_dist {
+                best = entry;
+                best_dist = dist;
+            }
+        }
+
+        best.0.clone()
+    }
+}
+
+/// Computes the "redmean" weighted color distance between two sRGB triples.
+///
+/// See <https://www.compuphase.com/cmetric.htm> for details on the formula.
+fn redmean_distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let r_bar = (a.0 as f64 + b.0 as f64) / 2.0;
+    let dr = a.0 as f64 - b.0 as f64;
+    let dg = a.1 as f64 - b.1 as f64;
+    let db = a.2 as f64 - b.2 as f64;
+
+    (2.0 + r_bar / 256.0) * dr * dr + 4.0 * dg * dg + (2.0 + (255.0 - r_bar) / 256.0) * db * db
+}
+
+/// Parses a `#rrggbb` (or `rrggbb`) hex string into the closest supported [`Color`].
+///
+/// Intended for use as a clap `value_parser`, e.g.
+/// `#[arg(value_parser = parse_hex_color)]`.
+#[cfg(feature = "clap")]
+pub fn parse_hex_color(s: &str) -> Result<Color, String> {
+    let hex = s.strip_prefix('#').unwrap_or(s);
+
+    if hex.len() != 6 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("'{s}' is not a valid #rrggbb hex color"));
+    }
+
+    let r = u8::from_str_radix(&hex[0..2], 16).map_err(|e| e.to_string())?;
+    let g = u8::from_str_radix(&hex[2..4], 16).map_err(|e| e.to_string())?;
+    let b = u8::from_str_radix(&hex[4..6], 16).map_err(|e| e.to_string())?;
+
+    Ok(Color::from_rgb(r, g, b))
+}
+
 /// Represents one of the three profiles of the keyboard
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum Profile {
     P1,
@@ -91,8 +208,20 @@ impl ToSkillerBytes for Profile {
     }
 }
 
+impl FromSkillerBytes for Profile {
+    fn from_skiller_bytes(byte: u8) -> Option<Profile> {
+        match byte {
+            1 => Some(Profile::P1),
+            2 => Some(Profile::P2),
+            3 => Some(Profile::P3),
+            _ => None,
+        }
+    }
+}
+
 /// Represents one of the possible brightness settings of the keyboard
 #[cfg_attr(feature = "clap", derive(clap::Subcommand))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum Brightness {
     /// A static color at the given brightness
@@ -107,6 +236,7 @@ pub enum Brightness {
 
 /// Represents one of four valid polling rates of the keyboard
 #[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub enum PollingRate {
     HZ125,
@@ -126,6 +256,18 @@ impl ToSkillerBytes for PollingRate {
     }
 }
 
+impl FromSkillerBytes for PollingRate {
+    fn from_skiller_bytes(byte: u8) -> Option<PollingRate> {
+        match byte {
+            8 => Some(PollingRate::HZ125),
+            4 => Some(PollingRate::HZ250),
+            2 => Some(PollingRate::HZ500),
+            1 => Some(PollingRate::HZ1000),
+            _ => None,
+        }
+    }
+}
+
 impl ToSkillerBytes for bool {
     fn to_skiller_bytes(&self) -> u8 {
         match self {
@@ -134,166 +276,3 @@ impl ToSkillerBytes for bool {
         }
     }
 }
-
-impl SkillerProPlus {
-    /// Creates a new SkillerProPlus struct with the given timeout.
-    /// The returned result will be an error if any libusb operations failed,
-    /// The nested Option indicates if the device is present on the USB bus
-    ///
-    /// ## Parameters
-    /// `timeout` specifies the usb timeout that is passed to libusb.
-    /// A sane value for this would be something like 2 seconds.
-    pub fn new(timeout: Duration) -> rusb::Result<Option<Self>> {
-        let context = Context::new()?;
-        let devices = context.devices()?;
-
-        for device in devices.iter() {
-            let device_desc = device.device_descriptor()?;
-
-            if device_desc.vendor_id() != VID || device_desc.product_id() != PID {
-                continue;
-            }
-
-            let mut handle = device.open()?;
-
-            // Detach the kernel driver if it is active.
-            // Not doing this causes libusb to return an IO error
-            if handle.kernel_driver_active(INTERFACE)? {
-                handle.detach_kernel_driver(INTERFACE)?;
-            }
-
-            return Ok(Some(SkillerProPlus {
-                handle: handle,
-                timeout,
-            }));
-        }
-        return Ok(None);
-    }
-
-    /// Sets the color of the keyboard for the given profile
-    /// Returns the amount of bytes written or any libusb errors
-    pub fn set_color(&self, color: Color, profile: Profile) -> rusb::Result<usize> {
-        let p = profile.to_skiller_bytes();
-
-        let payload: [u8; 8] = [
-            0x07,
-            0x0a,
-            p,
-            0x0a,
-            0x04,
-            0x00,
-            color.to_skiller_bytes(),
-            0x00,
-        ];
-
-        let mut total_written = 0;
-
-        total_written += self.skiller_write(&switch_profile(p))?;
-        total_written += self.skiller_write(&payload)?;
-
-        return Ok(total_written);
-    }
-
-    /// Sets the profile of the keyboard
-    ///
-    /// Returns the amount of bytes written or any error returned by libusb
-    pub fn set_profile(&self, profile: Profile) -> rusb::Result<usize> {
-        Ok(self.skiller_write(&switch_profile(profile.to_skiller_bytes()))?)
-    }
-
-    /// Sets the brightness and color for the given profile.
-    /// Returns the amount of bytes written or any error returned by libusb
-    ///
-    /// ## Note
-    /// You have to provide the color as well because the keyboards API is weird
-    pub fn set_brightness(&self, brightness: Brightness, profile: Profile) -> rusb::Result<usize> {
-        let p = profile.to_skiller_bytes();
-
-        let payload: [u8; 8] = match brightness {
-            Brightness::Static { level, color } => [
-                0x07,
-                0x0a,
-                p,
-                level,
-                0x04,
-                0x00,
-                color.to_skiller_bytes(),
-                0x00,
-            ],
-            Brightness::Pulsating { color } => [
-                0x07,
-                0x0a,
-                p,
-                11,
-                0x04,
-                0x00,
-                color.to_skiller_bytes(),
-                0x00,
-            ],
-            Brightness::Cycle => [0x07, 0x0a, p, 12, 0x04, 0x00, 0x00, 0x00],
-        };
-
-        let mut total_written = 0;
-
-        total_written += self.skiller_write(&switch_profile(p))?;
-        total_written += self.skiller_write(&payload)?;
-
-        return Ok(total_written);
-    }
-
-    /// Sets the global polling rate of the keyboard.
-    ///
-    /// Returns the amount of bytes written or any error returned by libusb.
-    pub fn set_polling_rate(&self, rate: PollingRate) -> rusb::Result<usize> {
-        Ok(self.skiller_write(&[
-            0x07,
-            0x01,
-            rate.to_skiller_bytes(),
-            0x00,
-            0x00,
-            0x00,
-            0x00,
-            0x00,
-        ])?)
-    }
-
-    /// Sets the windows key to be enabled or disabled
-    ///
-    /// Returns the amount of bytes written or any error returned by libusb
-    pub fn set_win_key(&self, enable: bool, profile: Profile) -> rusb::Result<usize> {
-        let p = profile.to_skiller_bytes();
-        let e = enable.to_skiller_bytes();
-
-        let payload = [0x07, 0x0b, p, e, 0x00, 0x00, 0x00, 0x00];
-
-        Ok(self.skiller_write(&payload)?)
-    }
-
-    /// Writes data to the keyboard
-    ///
-    /// Returns the amount of bytes written or any error returned by libusb
-    fn skiller_write(&self, data: &[u8; 8]) -> rusb::Result<usize> {
-        let rt = request_type(Direction::Out, RequestType::Class, Recipient::Interface);
-
-        let written = self
-            .handle
-            .write_control(rt, 9, 0x0307, 1, data, self.timeout)?;
-        return Ok(written);
-    }
-}
-
-fn switch_profile(profile: u8) -> [u8; 8] {
-    [0x07, 0x02, profile, 0x00, 0x00, 0x00, 0x00, 0x00]
-}
-
-// fn begin_handshake_profile(profile: u8) -> [u8; 8] {
-//     [0x07, 0x0b, profile, 0x00, 0x00, 0x00, 0x00, 0x00]
-// }
-
-// fn footer() -> [u8; 8] {
-//     let mut cmd = [0; 8];
-//     cmd[0] = 0x07;
-//     cmd[1] = 0x02;
-//     cmd[2] = 0x04;
-//     return cmd;
-// }
@@ -0,0 +1,299 @@
+//! The `SkillerProPlus` [`KeyboardBackend`]: Sharkoon's Skiller Pro+ keyboard.
+
+use crate::backend::enumerate_devices;
+use crate::{
+    Brightness, Color, FromSkillerBytes, KeyboardBackend, KeyboardConfig, PollingRate, Profile,
+    SkillerDevice, ToSkillerBytes,
+};
+use rusb::{request_type, Context, DeviceHandle, Direction, Recipient, RequestType};
+use std::time::Duration;
+
+const INTERFACE: u8 = 1;
+const VID: u16 = 0x04d9;
+const PID: u16 = 0xa096;
+
+/// A struct that can interact with the skiller pro plus keyboard
+///
+/// # Example
+/// ```
+/// use libskiller::{SkillerProPlus, Brightness, Color, Profile};
+/// use std::time::Duration;
+///
+/// let skiller = SkillerProPlus::new(Duration::from_secs(2))
+///     .unwrap() // unwrap() possible libusb errors, leaves Option<SkillerProPlus>
+///     .unwrap(); // If this is none, it means that no keyboard was found
+///
+/// skiller
+///     .set_color(Color::Red, Profile::P2)
+///     .unwrap();
+///
+/// skiller
+///     .set_brightness(Brightness::Pulsating {color: Color::Blue}, Profile::P3)
+///     .unwrap();
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct SkillerProPlus {
+    handle: DeviceHandle<Context>,
+    timeout: Duration,
+}
+
+impl KeyboardBackend for SkillerProPlus {
+    fn interface() -> u8 {
+        INTERFACE
+    }
+
+    fn vid_pid() -> (u16, u16) {
+        (VID, PID)
+    }
+
+    fn switch_profile(profile: &Profile) -> [u8; 8] {
+        [
+            0x07,
+            0x02,
+            profile.to_skiller_bytes(),
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+        ]
+    }
+
+    fn color_payload(color: &Color, profile: &Profile) -> [u8; 8] {
+        [
+            0x07,
+            0x0a,
+            profile.to_skiller_bytes(),
+            0x0a,
+            0x04,
+            0x00,
+            color.to_skiller_bytes(),
+            0x00,
+        ]
+    }
+
+    fn brightness_payload(brightness: &Brightness, profile: &Profile) -> [u8; 8] {
+        let p = profile.to_skiller_bytes();
+
+        match brightness {
+            Brightness::Static { level, color } => {
+                [0x07, 0x0a, p, *level, 0x04, 0x00, color.to_skiller_bytes(), 0x00]
+            }
+            Brightness::Pulsating { color } => {
+                [0x07, 0x0a, p, 11, 0x04, 0x00, color.to_skiller_bytes(), 0x00]
+            }
+            Brightness::Cycle => [0x07, 0x0a, p, 12, 0x04, 0x00, 0x00, 0x00],
+        }
+    }
+
+    fn polling_rate_payload(rate: &PollingRate) -> [u8; 8] {
+        [
+            0x07,
+            0x01,
+            rate.to_skiller_bytes(),
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+        ]
+    }
+
+    fn win_key_payload(enable: bool, profile: &Profile) -> [u8; 8] {
+        [
+            0x07,
+            0x0b,
+            profile.to_skiller_bytes(),
+            enable.to_skiller_bytes(),
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+        ]
+    }
+
+    fn parse_profile(report: &[u8; 8]) -> Option<Profile> {
+        if report[1] != 0x02 {
+            return None;
+        }
+
+        Profile::from_skiller_bytes(report[2])
+    }
+
+    fn parse_polling_rate(report: &[u8; 8]) -> Option<PollingRate> {
+        if report[1] != 0x01 {
+            return None;
+        }
+
+        PollingRate::from_skiller_bytes(report[2])
+    }
+}
+
+impl SkillerProPlus {
+    /// Creates a new SkillerProPlus struct with the given timeout.
+    /// The returned result will be an error if any libusb operations failed,
+    /// The nested Option indicates if the device is present on the USB bus
+    ///
+    /// ## Parameters
+    /// `timeout` specifies the usb timeout that is passed to libusb.
+    /// A sane value for this would be something like 2 seconds.
+    pub fn new(timeout: Duration) -> rusb::Result<Option<Self>> {
+        let device = match Self::enumerate(timeout)?.into_iter().next() {
+            Some(device) => device,
+            None => return Ok(None),
+        };
+
+        Ok(Some(Self::open(&device)?))
+    }
+
+    /// Enumerates all connected Skiller Pro+ keyboards without claiming any of them.
+    ///
+    /// Each returned [`SkillerDevice`] identifies one keyboard by USB bus number,
+    /// address and serial string. Pass one to [`SkillerProPlus::open`] to bind to it.
+    ///
+    /// ## Parameters
+    /// `timeout` specifies the usb timeout that is passed to libusb, both for this
+    /// call and for the later [`SkillerProPlus::open`] of any returned device.
+    pub fn enumerate(timeout: Duration) -> rusb::Result<Vec<SkillerDevice>> {
+        Ok(enumerate_devices(timeout)?
+            .into_iter()
+            .filter(|device| device.vid_pid() == Self::vid_pid())
+            .collect())
+    }
+
+    /// Binds to a specific keyboard previously discovered with [`SkillerProPlus::enumerate`].
+    ///
+    /// Unlike enumeration, this detaches any active kernel driver so the device can
+    /// be written to.
+    pub fn open(device: &SkillerDevice) -> rusb::Result<SkillerProPlus> {
+        let mut handle = device.device.open()?;
+
+        // Detach the kernel driver if it is active.
+        // Not doing this causes libusb to return an IO error
+        if handle.kernel_driver_active(INTERFACE)? {
+            handle.detach_kernel_driver(INTERFACE)?;
+        }
+
+        Ok(SkillerProPlus {
+            handle,
+            timeout: device.timeout,
+        })
+    }
+
+    /// Sets the color of the keyboard for the given profile
+    /// Returns the amount of bytes written or any libusb errors
+    pub fn set_color(&self, color: Color, profile: Profile) -> rusb::Result<usize> {
+        let mut total_written = 0;
+
+        total_written += self.skiller_write(&Self::switch_profile(&profile))?;
+        total_written += self.skiller_write(&Self::color_payload(&color, &profile))?;
+
+        Ok(total_written)
+    }
+
+    /// Sets the profile of the keyboard
+    ///
+    /// Returns the amount of bytes written or any error returned by libusb
+    pub fn set_profile(&self, profile: Profile) -> rusb::Result<usize> {
+        self.skiller_write(&Self::switch_profile(&profile))
+    }
+
+    /// Sets the brightness and color for the given profile.
+    /// Returns the amount of bytes written or any error returned by libusb
+    ///
+    /// ## Note
+    /// You have to provide the color as well because the keyboards API is weird
+    pub fn set_brightness(&self, brightness: Brightness, profile: Profile) -> rusb::Result<usize> {
+        let mut total_written = 0;
+
+        total_written += self.skiller_write(&Self::switch_profile(&profile))?;
+        total_written += self.skiller_write(&Self::brightness_payload(&brightness, &profile))?;
+
+        Ok(total_written)
+    }
+
+    /// Sets the global polling rate of the keyboard.
+    ///
+    /// Returns the amount of bytes written or any error returned by libusb.
+    pub fn set_polling_rate(&self, rate: PollingRate) -> rusb::Result<usize> {
+        self.skiller_write(&Self::polling_rate_payload(&rate))
+    }
+
+    /// Sets the windows key to be enabled or disabled
+    ///
+    /// Returns the amount of bytes written or any error returned by libusb
+    pub fn set_win_key(&self, enable: bool, profile: Profile) -> rusb::Result<usize> {
+        self.skiller_write(&Self::win_key_payload(enable, &profile))
+    }
+
+    /// Writes every setting in `config` to the keyboard, in profile-switch order,
+    /// and returns the total number of bytes written.
+    ///
+    /// This turns the one-setting-at-a-time calls above into a declarative
+    /// workflow suitable for applying a whole theme at once.
+    pub fn apply(&self, config: &KeyboardConfig) -> rusb::Result<usize> {
+        let mut total_written = 0;
+
+        for (profile, profile_config) in [
+            (Profile::P1, &config.p1),
+            (Profile::P2, &config.p2),
+            (Profile::P3, &config.p3),
+        ] {
+            total_written += self.set_color(profile_config.color.clone(), profile.clone())?;
+            total_written +=
+                self.set_brightness(profile_config.brightness.clone(), profile.clone())?;
+            total_written += self.set_win_key(profile_config.win_key, profile)?;
+        }
+
+        total_written += self.set_polling_rate(config.polling_rate.clone())?;
+
+        Ok(total_written)
+    }
+
+    /// Reads back the keyboard's feature report and checks whether it currently
+    /// reflects a profile switch.
+    ///
+    /// ## Note
+    /// There is one shared 8-byte feature report for the whole device, and it only
+    /// ever echoes whichever command was written most recently — it is not a
+    /// general-purpose read of "the current profile". In practice this is only
+    /// useful to confirm a just-issued `set_profile` actually reached the device;
+    /// it returns `None` if any other kind of command (e.g. `set_color`) was
+    /// written more recently. Untested against real hardware.
+    pub fn get_profile(&self) -> rusb::Result<Option<Profile>> {
+        Ok(Self::parse_profile(&self.skiller_read()?))
+    }
+
+    /// Reads back the keyboard's feature report and checks whether it currently
+    /// reflects a polling rate change.
+    ///
+    /// See the note on [`SkillerProPlus::get_profile`]: this is only useful to
+    /// confirm a just-issued `set_polling_rate` actually reached the device, not
+    /// to read the polling rate at an arbitrary point in time.
+    pub fn get_polling_rate(&self) -> rusb::Result<Option<PollingRate>> {
+        Ok(Self::parse_polling_rate(&self.skiller_read()?))
+    }
+
+    /// Writes data to the keyboard
+    ///
+    /// Returns the amount of bytes written or any error returned by libusb
+    fn skiller_write(&self, data: &[u8; 8]) -> rusb::Result<usize> {
+        let rt = request_type(Direction::Out, RequestType::Class, Recipient::Interface);
+
+        self.handle
+            .write_control(rt, 9, 0x0307, 1, data, self.timeout)
+    }
+
+    /// Reads the keyboard's current feature report
+    ///
+    /// Returns the 8-byte report or any error returned by libusb
+    fn skiller_read(&self) -> rusb::Result<[u8; 8]> {
+        let rt = request_type(Direction::In, RequestType::Class, Recipient::Interface);
+        let mut report = [0u8; 8];
+
+        self.handle
+            .read_control(rt, 1, 0x0307, 1, &mut report, self.timeout)?;
+
+        Ok(report)
+    }
+}